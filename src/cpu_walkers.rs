@@ -0,0 +1,177 @@
+use std::sync::Arc;
+
+use nannou::image::RgbaImage;
+use nannou::prelude::*;
+use rand::Rng;
+use rayon::prelude::*;
+
+use crate::Walker;
+
+/// Per-frame simulation parameters, mirroring `gpu::WalkerParams`.
+pub struct CpuStepParams {
+    pub width: f32,
+    pub height: f32,
+    pub turn_chance: f32,
+    pub turn_angle: f32,
+    pub division_chance: f32,
+    pub division_angle: f32,
+    pub speed: f32,
+    pub kill_threshold: u8,
+}
+
+/// CPU fallback for when the compute-shader path (`gpu::GpuWalkers`) isn't
+/// wanted or available. `prev_frame` is shared via `Arc` rather than
+/// cloned per walker, and every walker is stepped through a `rayon`
+/// parallel iterator instead of a `thread::spawn` per walker per frame.
+pub fn step(walkers: &[Walker], prev_frame: &Arc<RgbaImage>, params: &CpuStepParams) -> Vec<Walker> {
+    walkers
+        .par_iter()
+        .map(|w| step_one(w, prev_frame, params))
+        .flatten()
+        .filter(|w| !w.dead)
+        .collect()
+}
+
+fn step_one(walker: &Walker, prev_frame: &RgbaImage, params: &CpuStepParams) -> Vec<Walker> {
+    // thread-local: rayon reuses worker threads across frames, so this is
+    // effectively one RNG per worker rather than one per walker per frame.
+    let mut rng = rand::thread_rng();
+    let mut walker = walker.clone();
+    let mut new_walkers = vec![];
+
+    if rng.gen_range(0..100) as f32 / 100.0 < params.turn_chance {
+        turn(&mut walker, params.turn_angle, &mut rng);
+    }
+
+    if rng.gen_range(0..100) as f32 / 100.0 < params.division_chance {
+        let mut child = walker.clone();
+        turn(&mut child, params.division_angle, &mut rng);
+        child.generation += 1;
+        child.age = 0;
+        new_walkers.push(child);
+    }
+
+    walker.prev_position = walker.position;
+    walker.position = pt2(
+        walker.position.x + walker.velocity.x * params.speed,
+        walker.position.y + walker.velocity.y * params.speed,
+    );
+
+    let hwidth = params.width / 2.0;
+    if walker.position.x >= hwidth {
+        walker.position.x -= params.width;
+        walker.prev_position = walker.position;
+    } else if walker.position.x <= -hwidth {
+        walker.position.x += params.width;
+        walker.prev_position = walker.position;
+    }
+
+    let hheight = params.height / 2.0;
+    if walker.position.y >= hheight {
+        walker.position.y -= params.height;
+        walker.prev_position = walker.position;
+    } else if walker.position.y <= -hheight {
+        walker.position.y += params.height;
+        walker.prev_position = walker.position;
+    }
+
+    let img_width = prev_frame.width();
+    let img_height = prev_frame.height();
+    let pixel_x = map(walker.position.x, -hwidth, hwidth, 0.0, img_width as f32) as u32;
+    let pixel_y = map(walker.position.y, -hheight, hheight, 0.0, img_height as f32) as u32;
+    let pixel = prev_frame.get_pixel(
+        pixel_x.min(img_width - 1),
+        img_height - 1 - pixel_y.min(img_height - 1),
+    );
+
+    let avg = (pixel[0] as u32 + pixel[1] as u32 + pixel[2] as u32) / 3;
+    walker.sampled_luminance = avg as f32 / 255.0;
+    if avg >= params.kill_threshold as u32 {
+        walker.dead = true;
+    }
+
+    walker.age += 1;
+    new_walkers.push(walker);
+    new_walkers
+}
+
+fn turn(walker: &mut Walker, angle: f32, rng: &mut impl Rng) {
+    let factor = rng.gen_range(0..100) as f32 / 100.0 * 2.0 - 1.0;
+    walker.velocity = walker.velocity.rotate(angle * factor);
+}
+
+fn map(i: f32, in_min: f32, in_max: f32, out_min: f32, out_max: f32) -> f32 {
+    (i - in_min) / (in_max - in_min) * (out_max - out_min) + out_min
+}
+
+#[cfg(test)]
+mod tests {
+    use nannou::image::Rgba;
+
+    use super::*;
+
+    #[test]
+    fn map_scales_linearly() {
+        assert_eq!(map(0.0, 0.0, 10.0, 0.0, 100.0), 0.0);
+        assert_eq!(map(5.0, 0.0, 10.0, 0.0, 100.0), 50.0);
+        assert_eq!(map(10.0, 0.0, 10.0, 0.0, 100.0), 100.0);
+    }
+
+    #[test]
+    fn map_handles_negative_input_ranges() {
+        assert_eq!(map(-5.0, -10.0, 10.0, 0.0, 1.0), 0.25);
+    }
+
+    // turn_chance/division_chance at 0.0 keep `step_one` deterministic: the
+    // `rng.gen_range(0..100) as f32 / 100.0 < 0.0` checks they gate can
+    // never be true.
+    fn params() -> CpuStepParams {
+        CpuStepParams {
+            width: 10.0,
+            height: 10.0,
+            turn_chance: 0.0,
+            turn_angle: 0.0,
+            division_chance: 0.0,
+            division_angle: 0.0,
+            speed: 1.0,
+            kill_threshold: 255,
+        }
+    }
+
+    fn frame_of(color: [u8; 4]) -> Arc<RgbaImage> {
+        Arc::new(RgbaImage::from_pixel(4, 4, Rgba(color)))
+    }
+
+    #[test]
+    fn step_one_wraps_on_positive_x_edge() {
+        let walker = Walker::new(0, pt2(4.6, 0.0), pt2(1.0, 0.0));
+        let result = step_one(&walker, &frame_of([0, 0, 0, 255]), &params());
+        let stepped = &result[0];
+        assert!(stepped.position.x < 0.0, "expected wraparound, got {}", stepped.position.x);
+        assert_eq!(stepped.position, stepped.prev_position);
+    }
+
+    #[test]
+    fn step_one_wraps_on_negative_y_edge() {
+        let walker = Walker::new(0, pt2(0.0, -4.6), pt2(0.0, -1.0));
+        let result = step_one(&walker, &frame_of([0, 0, 0, 255]), &params());
+        let stepped = &result[0];
+        assert!(stepped.position.y > 0.0, "expected wraparound, got {}", stepped.position.y);
+    }
+
+    #[test]
+    fn step_one_kills_on_bright_pixel() {
+        let mut p = params();
+        p.kill_threshold = 10;
+        let walker = Walker::new(0, pt2(0.0, 0.0), pt2(0.0, 0.0));
+        let result = step_one(&walker, &frame_of([255, 255, 255, 255]), &p);
+        assert!(result[0].dead);
+    }
+
+    #[test]
+    fn step_one_survives_dark_pixel() {
+        let walker = Walker::new(0, pt2(0.0, 0.0), pt2(0.0, 0.0));
+        let result = step_one(&walker, &frame_of([0, 0, 0, 255]), &params());
+        assert!(!result[0].dead);
+    }
+}
@@ -0,0 +1,325 @@
+use nannou::wgpu;
+
+const WORKGROUP_SIZE: u32 = 64;
+
+/// GPU-side mirror of `Walker`, laid out to match `struct Walker` in
+/// `shaders/walkers.wgsl`. `dead` is stored as a `u32` because WGSL has no
+/// `bool` storage type, and `seed` drives the per-walker xorshift PRNG.
+/// `id` is inherited by children on division; `generation` counts divisions
+/// along a lineage; `sampled_luminance` is the previous-frame brightness
+/// the walker sampled this step, used for density-tinted coloring.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct GpuWalker {
+    pub position: [f32; 2],
+    pub prev_position: [f32; 2],
+    pub velocity: [f32; 2],
+    pub seed: u32,
+    pub dead: u32,
+    pub age: u32,
+    pub id: u32,
+    pub generation: u32,
+    pub sampled_luminance: f32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct Params {
+    width: f32,
+    height: f32,
+    turn_chance: f32,
+    turn_angle: f32,
+    division_chance: f32,
+    division_angle: f32,
+    speed: f32,
+    kill_threshold: f32,
+    walker_count: u32,
+    child_capacity: u32,
+    // align the uniform buffer to wgpu's 16-byte minimum binding alignment.
+    _padding: [u32; 2],
+}
+
+/// Holds the compute pipeline and GPU buffers backing the walker
+/// simulation. Walker state lives in `walker_buffer`; new walkers spawned
+/// by division land in `child_buffer`, sized by `child_capacity` and
+/// indexed via the atomic counter in `counter_buffer`.
+pub struct GpuWalkers {
+    pipeline: wgpu::ComputePipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    walker_buffer: wgpu::Buffer,
+    child_buffer: wgpu::Buffer,
+    counter_buffer: wgpu::Buffer,
+    params_buffer: wgpu::Buffer,
+    capacity: usize,
+    pub child_capacity: usize,
+}
+
+impl GpuWalkers {
+    pub fn new(device: &wgpu::Device, capacity: usize, child_capacity: usize) -> Self {
+        let shader = device.create_shader_module(&wgpu::include_wgsl!("shaders/walkers.wgsl"));
+
+        let walker_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("walker_buffer"),
+            size: (capacity * std::mem::size_of::<GpuWalker>()) as u64,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_SRC
+                | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let child_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("child_buffer"),
+            size: (child_capacity * std::mem::size_of::<GpuWalker>()) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        let counter_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("child_counter_buffer"),
+            size: std::mem::size_of::<u32>() as u64,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_SRC
+                | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let params_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("walker_params_buffer"),
+            size: std::mem::size_of::<Params>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("walkers_bind_group_layout"),
+            entries: &[
+                storage_entry(0, false),
+                storage_entry(1, false),
+                storage_entry(2, false),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("walkers_pipeline_layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("walkers_compute_pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: "main",
+        });
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            walker_buffer,
+            child_buffer,
+            counter_buffer,
+            params_buffer,
+            capacity,
+            child_capacity,
+        }
+    }
+
+    /// Uploads `walkers`, dispatches one invocation per walker against
+    /// `prev_frame_view`, and reads back the survivors plus any children
+    /// produced by division. `walkers.len()` must not exceed `capacity`.
+    pub fn step(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        prev_frame_view: &wgpu::TextureView,
+        walkers: &[GpuWalker],
+        params: WalkerParams,
+    ) -> Vec<GpuWalker> {
+        assert!(walkers.len() <= self.capacity, "walker count exceeds GPU buffer capacity");
+
+        queue.write_buffer(&self.walker_buffer, 0, bytemuck::cast_slice(walkers));
+        queue.write_buffer(&self.counter_buffer, 0, bytemuck::bytes_of(&0u32));
+
+        let uniform = Params {
+            width: params.width,
+            height: params.height,
+            turn_chance: params.turn_chance,
+            turn_angle: params.turn_angle,
+            division_chance: params.division_chance,
+            division_angle: params.division_angle,
+            speed: params.speed,
+            kill_threshold: params.kill_threshold,
+            walker_count: walkers.len() as u32,
+            child_capacity: self.child_capacity as u32,
+            _padding: [0; 2],
+        };
+        queue.write_buffer(&self.params_buffer, 0, bytemuck::bytes_of(&uniform));
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("walkers_bind_group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: self.walker_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: self.child_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.counter_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: self.params_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: wgpu::BindingResource::TextureView(prev_frame_view),
+                },
+            ],
+        });
+
+        let desc = wgpu::CommandEncoderDescriptor {
+            label: Some("walkers_compute_encoder"),
+        };
+        let mut encoder = device.create_command_encoder(&desc);
+
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("walkers_compute_pass"),
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            let workgroups = (walkers.len() as u32).div_ceil(WORKGROUP_SIZE);
+            pass.dispatch(workgroups.max(1), 1, 1);
+        }
+
+        let walker_bytes = std::mem::size_of_val(walkers) as u64;
+        let walker_readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("walker_readback_buffer"),
+            size: walker_bytes,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        encoder.copy_buffer_to_buffer(&self.walker_buffer, 0, &walker_readback, 0, walker_bytes);
+
+        let counter_readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("child_counter_readback_buffer"),
+            size: std::mem::size_of::<u32>() as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        encoder.copy_buffer_to_buffer(
+            &self.counter_buffer,
+            0,
+            &counter_readback,
+            0,
+            std::mem::size_of::<u32>() as u64,
+        );
+
+        queue.submit(Some(encoder.finish()));
+
+        let survivors: Vec<GpuWalker> = read_buffer(device, &walker_readback);
+        let child_count = read_buffer::<u32>(device, &counter_readback)[0] as usize;
+        let child_count = child_count.min(self.child_capacity);
+
+        let mut result = survivors;
+        if child_count > 0 {
+            let child_bytes = (child_count * std::mem::size_of::<GpuWalker>()) as u64;
+            let child_readback = device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("child_readback_buffer"),
+                size: child_bytes,
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            });
+
+            let desc = wgpu::CommandEncoderDescriptor {
+                label: Some("walkers_child_readback_encoder"),
+            };
+            let mut encoder = device.create_command_encoder(&desc);
+            encoder.copy_buffer_to_buffer(&self.child_buffer, 0, &child_readback, 0, child_bytes);
+            queue.submit(Some(encoder.finish()));
+
+            result.extend(read_buffer::<GpuWalker>(device, &child_readback));
+        }
+
+        result.retain(|w| w.dead == 0);
+        result
+    }
+}
+
+/// Per-frame simulation parameters, mirroring the fields on `Walkers`.
+pub struct WalkerParams {
+    pub width: f32,
+    pub height: f32,
+    pub turn_chance: f32,
+    pub turn_angle: f32,
+    pub division_chance: f32,
+    pub division_angle: f32,
+    pub speed: f32,
+    pub kill_threshold: f32,
+}
+
+fn storage_entry(binding: u32, read_only: bool) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::COMPUTE,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Storage { read_only },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    }
+}
+
+/// Maps `buffer` for reading and blocks until the data is available. Used
+/// for the once-per-frame walker/counter readback, small enough that a
+/// synchronous wait doesn't meaningfully stall the render loop.
+fn read_buffer<T: bytemuck::Pod>(device: &wgpu::Device, buffer: &wgpu::Buffer) -> Vec<T> {
+    let slice = buffer.slice(..);
+    let mapped = slice.map_async(wgpu::MapMode::Read);
+    device.poll(wgpu::Maintain::Wait);
+    futures::executor::block_on(mapped).expect("failed to map buffer for reading");
+
+    let data = bytemuck::cast_slice(&slice.get_mapped_range()).to_vec();
+    buffer.unmap();
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    // `device.create_shader_module` only ever runs against a real adapter,
+    // so `cargo build`/`cargo test` alone never parse `walkers.wgsl` -- this
+    // feeds it straight through the pinned naga front end to catch syntax
+    // the running app would otherwise only discover by failing to start.
+    #[test]
+    fn walkers_shader_parses() {
+        let source = include_str!("shaders/walkers.wgsl");
+        naga::front::wgsl::parse_str(source).expect("walkers.wgsl failed to parse under the pinned naga front end");
+    }
+}
+
@@ -1,47 +1,172 @@
+use std::path::PathBuf;
 use std::sync::mpsc::{Sender, Receiver};
 use std::sync::mpsc::channel;
-use std::thread;
+use std::sync::Arc;
 
 use nannou::prelude::*;
+use nannou_egui::{egui, Egui};
 use rand::Rng;
 
+mod cpu_walkers;
+mod gpu;
+mod recorder;
+mod render_target;
+
+use cpu_walkers::CpuStepParams;
+use gpu::{GpuWalker, GpuWalkers, WalkerParams};
+use recorder::Recorder;
+use render_target::{OffscreenRenderTarget, RenderTarget, WindowedRenderTarget};
+
 const WIDTH: u32 = 889;
 const HEIGHT: u32 = 500;
 
+// Upper bound on live walkers and on children produced by division in a
+// single frame. The GPU buffers backing the simulation are sized once
+// against these, so growth beyond them is simply dropped.
+const MAX_WALKERS: usize = 20_000;
+const MAX_CHILDREN_PER_FRAME: usize = 4_096;
+
+/// Parsed from CLI flags; `batch` is set by `--headless` and drives a
+/// fixed-frame-count run with no interactive preview. `render_size`
+/// defaults to the window size but can be raised independently (e.g.
+/// `--resolution 4000x4000`) to print-quality stills.
+struct AppConfig {
+    render_width: u32,
+    render_height: u32,
+    batch: Option<BatchConfig>,
+    cpu_fallback: bool,
+}
+
+struct BatchConfig {
+    frames_remaining: u32,
+    output: PathBuf,
+}
+
+fn parse_args() -> AppConfig {
+    let args: Vec<String> = std::env::args().collect();
+    let mut render_width = WIDTH;
+    let mut render_height = HEIGHT;
+    let mut batch = None;
+    let mut cpu_fallback = false;
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--resolution" => {
+                if let Some((w, h)) = args.get(i + 1).and_then(|s| s.split_once('x')) {
+                    render_width = w.parse().unwrap_or(WIDTH);
+                    render_height = h.parse().unwrap_or(HEIGHT);
+                }
+                i += 2;
+            }
+            "--headless" => {
+                let frames = args.get(i + 1).and_then(|s| s.parse().ok()).unwrap_or(300);
+                let output = args
+                    .get(i + 2)
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| PathBuf::from("crystal.png"));
+                batch = Some(BatchConfig {
+                    frames_remaining: frames,
+                    output,
+                });
+                i += 3;
+            }
+            // Run the walker simulation on the CPU via rayon instead of the
+            // GPU compute pipeline, e.g. on systems without compute shader support.
+            "--cpu-fallback" => {
+                cpu_fallback = true;
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+
+    AppConfig {
+        render_width,
+        render_height,
+        batch,
+        cpu_fallback,
+    }
+}
+
 fn main() {
-    nannou::app(model)
-        .update(update)
-        .simple_window(view)
-        .size(WIDTH, HEIGHT)
-        .run();
+    // No `.simple_window`/`.size` here: whether a window gets created at all
+    // is decided in `model`, based on whether `--headless` was passed, so
+    // batch runs never open one.
+    nannou::app(model).update(update).run();
 }
 
 struct Model {
     walkers: Walkers,
     first_run: bool,
-    main_window_id: WindowId,
-    texture: wgpu::Texture,
+    device_queue: Arc<wgpu::DeviceQueuePair>,
+    render_target: Box<dyn RenderTarget>,
     texture_capturer: wgpu::TextureCapturer,
-    texture_reshaper: wgpu::TextureReshaper,
     renderer: nannou::draw::Renderer,
     image_sender: Sender<nannou::image::RgbaImage>,
     image_receiver: Receiver<nannou::image::RgbaImage>,
     draw: nannou::Draw,
+    recorder: Recorder,
+    egui: Option<Egui>,
+    paused: bool,
+    batch: Option<BatchConfig>,
 }
 
 fn model(app: &App) -> Model {
-    let main_window_id = app.new_window().size(WIDTH, HEIGHT).view(view).build().unwrap();
-    let window = app.window(main_window_id).unwrap();
-    let device = window.device();
-    let msaa_samples = window.msaa_samples();
-
-    let size = pt2((WIDTH) as f32, (HEIGHT) as f32);
-    let texture = create_app_texture(device, size, msaa_samples);
-    let texture_reshaper = create_texture_reshaper(device, &texture, msaa_samples);
+    let config = parse_args();
+
+    // In batch mode no window (and therefore no visible surface) is ever
+    // created -- the device/queue are requested directly from the adapter,
+    // the same way `window::Builder` does internally, just without a
+    // `wgpu::Surface` to be compatible with. `render_target` then runs the
+    // whole walker/texture/capture loop against that device with nothing to
+    // present (`OffscreenRenderTarget::present` is a no-op).
+    let (device_queue, msaa_samples, egui) = if config.batch.is_some() {
+        let adapter = app
+            .wgpu_adapters()
+            .get_or_request(wgpu::RequestAdapterOptions::default(), app.instance())
+            .expect("no wgpu adapter available for headless batch mode");
+        let device_queue = adapter.get_or_request_device(wgpu::default_device_descriptor());
+        (device_queue, 1, None)
+    } else {
+        let window_id = app
+            .new_window()
+            .size(WIDTH, HEIGHT)
+            .view(view)
+            .key_pressed(key_pressed)
+            .raw_event(raw_window_event)
+            .build()
+            .unwrap();
+        let window = app.window(window_id).unwrap();
+        let device_queue = window.device_queue_pair().clone();
+        let msaa_samples = window.msaa_samples();
+        let egui = Some(Egui::from_window(&window));
+        (device_queue, msaa_samples, egui)
+    };
+    let device = device_queue.device();
+
+    // The walker/texture/capture loop runs at `render_target`'s resolution,
+    // independent of the (fixed) window size -- the window only ever shows
+    // a preview of it. In headless batch mode there's nothing to preview.
+    let render_target: Box<dyn RenderTarget> = if config.batch.is_some() {
+        Box::new(OffscreenRenderTarget::new(
+            device,
+            config.render_width,
+            config.render_height,
+        ))
+    } else {
+        Box::new(WindowedRenderTarget::new(
+            device,
+            config.render_width,
+            config.render_height,
+            msaa_samples,
+        ))
+    };
+    let size = pt2(config.render_width as f32, config.render_height as f32);
 
     // Create our `Draw` instance and a renderer for it.
     let draw = nannou::Draw::new();
-    let descriptor = texture.descriptor();
+    let descriptor = render_target.texture().descriptor();
     let renderer =
         nannou::draw::RendererBuilder::new().build_from_texture_descriptor(device, descriptor);
 
@@ -53,31 +178,94 @@ fn model(app: &App) -> Model {
     };
     let encoder = device.create_command_encoder(&desc);
 
-    window.queue().submit([encoder.finish()]);
+    device_queue.queue().submit([encoder.finish()]);
 
     let (image_sender, image_receiver) = channel();
 
+    let recorder = Recorder::new(app.project_path().unwrap());
+
     Model {
-        walkers: Walkers::new(0.5, size[0], size[1]),
+        walkers: Walkers::new(device, 0.5, size[0], size[1], !config.cpu_fallback),
         first_run: true,
-        main_window_id,
-        texture,
+        device_queue,
+        render_target,
         texture_capturer,
-        texture_reshaper,
         renderer,
         image_sender,
         image_receiver,
         draw,
+        recorder,
+        egui,
+        paused: false,
+        batch: config.batch,
     }
 }
 
-fn update(app: &App, model: &mut Model, _update: Update) {
+fn update(app: &App, model: &mut Model, update: Update) {
+    let device = model.device_queue.device();
+    let queue = model.device_queue.queue();
+
+    // Headless batch runs have no window, and so no egui panel.
+    if let Some(egui) = model.egui.as_mut() {
+        egui.set_elapsed_time(update.since_start);
+        let ctx = egui.begin_frame();
+        egui::Window::new("walkers").show(&ctx, |ui| {
+            ui.add(egui::Slider::new(&mut model.walkers.turn_chance, 0.0..=1.0).text("turn chance"));
+            ui.add(egui::Slider::new(&mut model.walkers.turn_angle, 0.0..=std::f32::consts::PI).text("turn angle"));
+            ui.add(egui::Slider::new(&mut model.walkers.division_chance, 0.0..=0.01).text("division chance"));
+            ui.add(egui::Slider::new(&mut model.walkers.division_angle, 0.0..=std::f32::consts::PI).text("division angle"));
+            ui.add(egui::Slider::new(&mut model.walkers.speed, 0.0..=5.0).text("speed"));
+            ui.add(egui::Slider::new(&mut model.walkers.kill_threshold, 0..=255).text("kill threshold"));
+            ui.add(egui::Slider::new(&mut model.walkers.line_weight, 0.1..=5.0).text("line weight"));
+
+            egui::ComboBox::from_label("color mode")
+                .selected_text(format!("{:?}", model.walkers.color_mode))
+                .show_ui(ui, |ui| {
+                    for mode in [
+                        ColorMode::Age,
+                        ColorMode::Generation,
+                        ColorMode::Heading,
+                        ColorMode::Density,
+                    ] {
+                        ui.selectable_value(&mut model.walkers.color_mode, mode, format!("{:?}", mode));
+                    }
+                });
+
+            ui.separator();
+            ui.add(egui::Slider::new(&mut model.recorder.frame_skip, 1..=30).text("frame skip"));
+            ui.add(egui::Slider::new(&mut model.recorder.max_frames, 1..=2000).text("max frames"));
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button(if model.paused { "resume" } else { "pause" }).clicked() {
+                    model.paused = !model.paused;
+                }
+                if ui.button("reseed").clicked() {
+                    model.walkers.reset();
+                    model.first_run = true;
+                }
+            });
+        });
+
+        if model.paused {
+            return;
+        }
+    }
+
     if let Ok(image) = model.image_receiver.try_recv() {
-        // let path = app.project_path().unwrap().join("frame").with_extension("png");
-        // image.save(path).ok();
-        model.walkers.update(&image);
+        model.recorder.push(&image);
+        model.walkers.update(device, queue, &image);
+
+        if let Some(batch) = model.batch.as_mut() {
+            batch.frames_remaining = batch.frames_remaining.saturating_sub(1);
+            if batch.frames_remaining == 0 {
+                image.save(&batch.output).expect("failed to save final batch frame");
+                app.quit();
+                return;
+            }
+        }
     }
-    
+
     // prepare to draw.
     let draw = &model.draw;
     draw.reset();
@@ -88,10 +276,7 @@ fn update(app: &App, model: &mut Model, _update: Update) {
         model.first_run = false;
     }
 
-    model.walkers.draw(&draw);
-
-    let window = app.window(model.main_window_id).unwrap();
-    let device = window.device();
+    model.walkers.draw(draw);
 
     // setup encoder
     let desc = wgpu::CommandEncoderDescriptor {
@@ -101,19 +286,20 @@ fn update(app: &App, model: &mut Model, _update: Update) {
 
     model
         .renderer
-        .render_to_texture(device, &mut encoder, &draw, &model.texture);
+        .render_to_texture(device, &mut encoder, draw, model.render_target.texture());
 
     // Take a snapshot of the texture. The capturer will do the following:
     //
     // 1. Resolve the texture to a non-multisampled texture if necessary.
     // 2. Convert the format to non-linear 8-bit sRGBA ready for image storage.
     // 3. Copy the result to a buffer ready to be mapped for reading.
-    let snapshot = model
-        .texture_capturer
-        .capture(device, &mut encoder, &model.texture);
+    let snapshot =
+        model
+            .texture_capturer
+            .capture(device, &mut encoder, model.render_target.texture());
 
     // Submit the commands for our drawing and texture capture to the GPU.
-    window.queue().submit(Some(encoder.finish()));
+    queue.submit(Some(encoder.finish()));
 
     let sender = model.image_sender.clone();
 
@@ -125,12 +311,44 @@ fn update(app: &App, model: &mut Model, _update: Update) {
         .unwrap();
 }
 
-fn view(app: &App, model: &Model, frame: Frame) {
-    // Sample the texture and write it to the frame.
-    let mut encoder = frame.command_encoder();
-    model
-        .texture_reshaper
-        .encode_render_pass(frame.texture_view(), &mut *encoder);
+fn view(_app: &App, model: &Model, frame: Frame) {
+    // Sample the render target's texture and write it to the frame. Only
+    // registered on the preview window, so `egui` is always present here.
+    model.render_target.present(&frame);
+
+    if let Some(egui) = &model.egui {
+        egui.draw_to_frame(&frame).unwrap();
+    }
+}
+
+fn raw_window_event(_app: &App, model: &mut Model, event: &nannou::winit::event::WindowEvent) {
+    if let Some(egui) = model.egui.as_mut() {
+        egui.handle_raw_event(event);
+    }
+}
+
+fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+    match key {
+        // start/stop buffering frames
+        Key::R => model.recorder.toggle(),
+        // dump the buffered frames as a looping gif
+        Key::G => model.recorder.save_gif(),
+        // dump the buffered frames as a png sequence
+        Key::P => model.recorder.save_png_sequence(),
+        _ => {}
+    }
+}
+
+/// Selects what a walker's trail color is keyed on. `Age` and `Generation`
+/// cycle hue through HSV; `Heading` maps the walker's direction of travel
+/// straight to hue; `Density` tints toward the brightness the walker
+/// sampled from the previous frame, so denser regions shift hue.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ColorMode {
+    Age,
+    Generation,
+    Heading,
+    Density,
 }
 
 struct Walkers {
@@ -144,130 +362,132 @@ struct Walkers {
     height: f32,
     kill_threshold: u8,
     line_weight: f32,
+    color_mode: ColorMode,
+    use_gpu: bool,
+    gpu: GpuWalkers,
+    sample_texture: wgpu::Texture,
 }
 
 impl Walkers {
-    pub fn new(speed: f32, width: f32, height: f32) -> Self {
+    pub fn new(device: &wgpu::Device, speed: f32, width: f32, height: f32, use_gpu: bool) -> Self {
+        let sample_texture = wgpu::TextureBuilder::new()
+            .size([width as u32, height as u32])
+            .usage(wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST)
+            .format(wgpu::TextureFormat::Rgba8Unorm)
+            .build(device);
+
         Self {
-            walkers: vec![Walker::new(pt2(0.0, height * -0.5), pt2(0.0, 1.0)), Walker::new(pt2(width * -0.5, 0.0), pt2(1.0, 0.0))],
+            walkers: vec![Walker::new(0, pt2(0.0, height * -0.5), pt2(0.0, 1.0)), Walker::new(1, pt2(width * -0.5, 0.0), pt2(1.0, 0.0))],
             turn_chance: 0.01,
-            turn_angle: 1.0471975512, // pi / 3
+            turn_angle: std::f32::consts::FRAC_PI_3,
             division_chance: 0.000000,
-            division_angle: 0.7853981634, // pi / 4
+            division_angle: std::f32::consts::FRAC_PI_4,
             speed,
             width,
             height,
             kill_threshold: 150,
             line_weight: 1.0,
+            color_mode: ColorMode::Heading,
+            use_gpu,
+            gpu: GpuWalkers::new(device, MAX_WALKERS, MAX_CHILDREN_PER_FRAME),
+            sample_texture,
         }
     }
 
-    pub fn update(&mut self, prev_frame: &nannou::image::RgbaImage) {
-        let (tx, rx) = channel();
-        let mut children = vec![];
-
-        for w in self.walkers.iter() {
-            let mut walker = w.clone();
-            // turn walkers
-            let thread_tx = tx.clone();
-            let img = prev_frame.clone();
-            let width = self.width;
-            let height = self.height;
-            let turn_chance = self.turn_chance;
-            let turn_angle = self.turn_angle;
-            let division_chance = self.division_chance;
-            let division_angle = self.division_angle;
-            let speed = self.speed;
-            let kill_threshold = self.kill_threshold;
-
-            let child = thread::spawn(move || {
-                let mut new_walkers = vec![];
-                let img_width = img.width();
-                let img_height = img.height();
-
-                let turn_value = rand::thread_rng().gen_range(0..100) as f32 / 100.0;
-                if turn_value < turn_chance {
-                    walker.turn(turn_angle);
-                }
-
-                // divide walkers
-                let div_value = rand::thread_rng().gen_range(0..100) as f32 / 100.0;
-                if div_value < division_chance {
-                    let mut child = walker.clone();
-                    child.turn(division_angle);
-                    new_walkers.push(child);
-                }
-
-                // update walker position
-                walker.update(speed);
-
-                // wrap around canvas
-                let hwidth = width / 2.0;
-                if walker.position.x >= hwidth {
-                    walker.position.x -= width;
-                    walker.prev_position = walker.position;
-                } else if walker.position.x <= -hwidth {
-                    walker.position.x += width;
-                    walker.prev_position = walker.position;
-                }
-
-                let hheight = height / 2.0;
-                if walker.position.y >= hheight {
-                    walker.position.y -= height;
-                    walker.prev_position = walker.position;
-                } else if walker.position.y <= -hheight {
-                    walker.position.y += height;
-                    walker.prev_position = walker.position;
-                }
-
-                let pixel_x = map(walker.position.x, -hwidth, hwidth, 0.0, img_width as f32) as u32;
-                let pixel_y =
-                    map(walker.position.y, -hheight, hheight, 0.0, img_height as f32) as u32;
-                let pixel = img.get_pixel(
-                    pixel_x.min(img_width - 1),
-                    img_height - 1 - pixel_y.min(img_height - 1),
-                );
-                
-                let avg = (pixel[0] + pixel[1] + pixel[3]) / 3;
-                println!("{:?}", avg);
-                if avg >= kill_threshold {
-                    walker.dead = true;
-                }
-
-                new_walkers.push(walker);
-
-                thread_tx.send(new_walkers).unwrap();
-            });
-
-            children.push(child);
+    /// Advances every walker one step. By default this dispatches the GPU
+    /// compute pipeline (`gpu::GpuWalkers`); with `use_gpu` false it falls
+    /// back to the `cpu_walkers` rayon pass for systems without compute
+    /// shader support.
+    pub fn update(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        prev_frame: &nannou::image::RgbaImage,
+    ) {
+        if self.use_gpu {
+            self.update_gpu(device, queue, prev_frame);
+        } else {
+            self.update_cpu(prev_frame);
         }
+    }
 
-        self.walkers = vec![];
-        for _ in 0..children.len() {
-            let mut new_walkers: Vec<Walker> = rx
-                .recv()
-                .unwrap()
-                .iter()
-                .filter(|w| !w.dead)
-                .map(|w| w.clone())
-                .collect();
-            self.walkers.append(&mut new_walkers);
-        }
+    fn update_gpu(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        prev_frame: &nannou::image::RgbaImage,
+    ) {
+        queue.write_texture(
+            self.sample_texture.as_image_copy(),
+            prev_frame,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: std::num::NonZeroU32::new(4 * prev_frame.width()),
+                rows_per_image: std::num::NonZeroU32::new(prev_frame.height()),
+            },
+            self.sample_texture.extent(),
+        );
+        let view = self.sample_texture.view().build();
+
+        let gpu_walkers: Vec<GpuWalker> = self
+            .walkers
+            .iter()
+            .map(|w| w.to_gpu())
+            .collect();
+
+        let params = WalkerParams {
+            width: self.width,
+            height: self.height,
+            turn_chance: self.turn_chance,
+            turn_angle: self.turn_angle,
+            division_chance: self.division_chance,
+            division_angle: self.division_angle,
+            speed: self.speed,
+            kill_threshold: self.kill_threshold as f32 / 255.0,
+        };
+
+        let result = self.gpu.step(device, queue, &view, &gpu_walkers, params);
+
+        self.walkers = result
+            .into_iter()
+            .take(MAX_WALKERS)
+            .map(Walker::from_gpu)
+            .collect();
+    }
 
-        for child in children {
-            child.join().expect("oops! the child thread panicked");
-        }
+    fn update_cpu(&mut self, prev_frame: &nannou::image::RgbaImage) {
+        let prev_frame = Arc::new(prev_frame.clone());
+        let params = CpuStepParams {
+            width: self.width,
+            height: self.height,
+            turn_chance: self.turn_chance,
+            turn_angle: self.turn_angle,
+            division_chance: self.division_chance,
+            division_angle: self.division_angle,
+            speed: self.speed,
+            kill_threshold: self.kill_threshold,
+        };
+
+        self.walkers = cpu_walkers::step(&self.walkers, &prev_frame, &params)
+            .into_iter()
+            .take(MAX_WALKERS)
+            .collect();
     }
 
     pub fn draw(&self, draw: &Draw) {
         for walker in self.walkers.iter() {
-            walker.draw(draw, self.line_weight);
+            walker.draw(draw, self.line_weight, self.color_mode);
         }
     }
-}
 
-fn map(i: f32, in_min: f32, in_max: f32, out_min: f32, out_max: f32) -> f32 {
-    return (i - in_min) / (in_max - in_min) * (out_max - out_min) + out_min;
+    /// Clears the walker set back to the two starting walkers. Pairs with
+    /// `model.first_run = true` so the canvas texture is cleared too.
+    pub fn reset(&mut self) {
+        self.walkers = vec![
+            Walker::new(0, pt2(0.0, self.height * -0.5), pt2(0.0, 1.0)),
+            Walker::new(1, pt2(self.width * -0.5, 0.0), pt2(1.0, 0.0)),
+        ];
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -276,71 +496,74 @@ struct Walker {
     pub prev_position: Vec2,
     pub velocity: Vec2,
     pub dead: bool,
+    seed: u32,
+    age: u32,
+    id: u32,
+    generation: u32,
+    sampled_luminance: f32,
 }
 
 impl Walker {
-    pub fn new(position: Vec2, velocity: Vec2) -> Self {
+    pub fn new(id: u32, position: Vec2, velocity: Vec2) -> Self {
         Self {
             position,
             prev_position: position,
             velocity,
             dead: false,
+            seed: rand::thread_rng().gen_range(1..u32::MAX),
+            age: 0,
+            id,
+            generation: 0,
+            sampled_luminance: 0.0,
         }
     }
 
-    pub fn turn(&mut self, angle: f32) {
-        let factor = rand::thread_rng().gen_range(0..100) as f32 / 100.0 * 2.0 - 1.0;
-        self.velocity = self.velocity.rotate(angle * factor);
+    pub fn draw(&self, draw: &Draw, weight: f32, color_mode: ColorMode) {
+        draw.line()
+            .start(self.prev_position)
+            .end(self.position)
+            .weight(weight)
+            .color(self.color(color_mode));
     }
 
-    pub fn next_position(&mut self, speed: f32) -> Vec2 {
-        pt2(
-            self.position.x + self.velocity.x * speed,
-            self.position.y + self.velocity.y * speed,
-        )
+    fn color(&self, mode: ColorMode) -> Hsv {
+        match mode {
+            ColorMode::Age => hsv((self.age as f32 * 0.01) % 1.0, 0.8, 1.0),
+            ColorMode::Generation => hsv((self.generation as f32 * 0.15) % 1.0, 0.8, 1.0),
+            ColorMode::Heading => {
+                let heading = self.velocity.y.atan2(self.velocity.x);
+                hsv((heading / std::f32::consts::TAU).rem_euclid(1.0), 0.8, 1.0)
+            }
+            ColorMode::Density => hsv(self.sampled_luminance.clamp(0.0, 1.0), 0.9, 1.0),
+        }
     }
 
-    pub fn update(&mut self, speed: f32) {
-        self.prev_position = self.position.clone();
-        self.position = self.next_position(speed);
+    fn to_gpu(&self) -> GpuWalker {
+        GpuWalker {
+            position: self.position.to_array(),
+            prev_position: self.prev_position.to_array(),
+            velocity: self.velocity.to_array(),
+            seed: self.seed,
+            dead: self.dead as u32,
+            age: self.age,
+            id: self.id,
+            generation: self.generation,
+            sampled_luminance: self.sampled_luminance,
+        }
     }
 
-    pub fn draw(&self, draw: &Draw, weight: f32) {
-        draw.line()
-            .start(self.prev_position)
-            .end(self.position)
-            .weight(weight)
-            .color(WHITE);
+    fn from_gpu(gpu: GpuWalker) -> Self {
+        Self {
+            position: gpu.position.into(),
+            prev_position: gpu.prev_position.into(),
+            velocity: gpu.velocity.into(),
+            dead: gpu.dead != 0,
+            seed: gpu.seed,
+            age: gpu.age,
+            id: gpu.id,
+            generation: gpu.generation,
+            sampled_luminance: gpu.sampled_luminance,
+        }
     }
 }
 
-fn create_app_texture(device: &wgpu::Device, size: Point2, msaa_samples: u32) -> wgpu::Texture {
-    wgpu::TextureBuilder::new()
-        .size([size[0] as u32, size[1] as u32])
-        .usage(
-            wgpu::TextureUsages::RENDER_ATTACHMENT
-                | wgpu::TextureUsages::TEXTURE_BINDING
-                | wgpu::TextureUsages::COPY_SRC,
-        )
-        .sample_count(msaa_samples)
-        .format(Frame::TEXTURE_FORMAT)
-        .build(device)
-}
-
-fn create_texture_reshaper(
-    device: &wgpu::Device,
-    texture: &wgpu::Texture,
-    msaa_samples: u32,
-) -> wgpu::TextureReshaper {
-    let texture_view = texture.view().build();
-    let texture_component_type = texture.sample_type();
-    let dst_format = Frame::TEXTURE_FORMAT;
-    wgpu::TextureReshaper::new(
-        device,
-        &texture_view,
-        msaa_samples,
-        texture_component_type,
-        msaa_samples,
-        dst_format,
-    )
-}
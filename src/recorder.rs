@@ -0,0 +1,179 @@
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+
+use nannou::image::codecs::gif::{GifEncoder, Repeat};
+use nannou::image::{Delay, Frame as GifFrame, RgbaImage};
+
+/// Buffers captured frames while recording and flushes them to disk
+/// either as a zero-padded PNG sequence or as a looping animated GIF.
+///
+/// Frames arrive asynchronously from `image_receiver`, so `push` is the
+/// only mutation point: it keeps frames in arrival order, applies
+/// `frame_skip`, and stops accumulating once `max_frames` is hit so long
+/// crystallization runs don't grow the buffer without bound.
+pub struct Recorder {
+    pub recording: bool,
+    pub frame_skip: usize,
+    pub max_frames: usize,
+    pub gif_frame_delay_ms: u32,
+    output_dir: PathBuf,
+    frames: Vec<RgbaImage>,
+    frames_seen: usize,
+}
+
+impl Recorder {
+    pub fn new(output_dir: PathBuf) -> Self {
+        Self {
+            recording: false,
+            frame_skip: 1,
+            max_frames: 600,
+            gif_frame_delay_ms: 33,
+            output_dir,
+            frames: vec![],
+            frames_seen: 0,
+        }
+    }
+
+    /// Starts or stops recording. Stopping does not flush to disk -- call
+    /// `save_png_sequence` or `save_gif` (e.g. from a key binding) to do that.
+    pub fn toggle(&mut self) {
+        self.recording = !self.recording;
+        if self.recording {
+            self.frames.clear();
+            self.frames_seen = 0;
+        }
+    }
+
+    pub fn push(&mut self, image: &RgbaImage) {
+        if !self.recording || self.frames.len() >= self.max_frames {
+            return;
+        }
+
+        self.frames_seen += 1;
+        // `frame_skip` is `pub` so the egui panel can drive it directly;
+        // clamp to 1 here rather than there so a stray 0 can never reach
+        // this modulo and panic.
+        if !(self.frames_seen - 1).is_multiple_of(self.frame_skip.max(1)) {
+            return;
+        }
+
+        self.frames.push(image.clone());
+    }
+
+    /// Encodes the buffered frames as `frames/frame_00000.png`, `frame_00001.png`, ...
+    /// on a background thread so the render loop isn't stalled while encoding.
+    pub fn save_png_sequence(&mut self) {
+        let frames = std::mem::take(&mut self.frames);
+        if frames.is_empty() {
+            return;
+        }
+
+        let dir = self.output_dir.join("frames");
+        thread::spawn(move || {
+            fs::create_dir_all(&dir).expect("failed to create frames directory");
+            for (i, frame) in frames.iter().enumerate() {
+                let path = dir.join(format!("frame_{:05}.png", i));
+                frame.save(path).expect("failed to save frame");
+            }
+        });
+    }
+
+    /// Encodes the buffered frames as a single looping GIF on a background thread.
+    pub fn save_gif(&mut self) {
+        let frames = std::mem::take(&mut self.frames);
+        if frames.is_empty() {
+            return;
+        }
+
+        let path = self.output_dir.join("walkers.gif");
+        let delay_ms = self.gif_frame_delay_ms;
+        thread::spawn(move || {
+            fs::create_dir_all(path.parent().unwrap()).expect("failed to create output directory");
+            let file = fs::File::create(&path).expect("failed to create gif file");
+            let mut encoder = GifEncoder::new(file);
+            encoder.set_repeat(Repeat::Infinite).ok();
+
+            for image in frames {
+                let delay = Delay::from_saturating_duration(std::time::Duration::from_millis(delay_ms as u64));
+                let gif_frame = GifFrame::from_parts(image, 0, 0, delay);
+                encoder.encode_frame(gif_frame).expect("failed to encode gif frame");
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recorder() -> Recorder {
+        Recorder::new(PathBuf::from("/tmp"))
+    }
+
+    fn blank_frame() -> RgbaImage {
+        RgbaImage::new(1, 1)
+    }
+
+    #[test]
+    fn push_is_ignored_while_not_recording() {
+        let mut r = recorder();
+        r.push(&blank_frame());
+        assert_eq!(r.frames.len(), 0);
+    }
+
+    #[test]
+    fn push_buffers_every_frame_by_default() {
+        let mut r = recorder();
+        r.toggle();
+        for _ in 0..5 {
+            r.push(&blank_frame());
+        }
+        assert_eq!(r.frames.len(), 5);
+    }
+
+    #[test]
+    fn push_applies_frame_skip() {
+        let mut r = recorder();
+        r.toggle();
+        r.frame_skip = 3;
+        for _ in 0..9 {
+            r.push(&blank_frame());
+        }
+        // frames_seen 1, 4, 7 are kept: (n - 1) % 3 == 0
+        assert_eq!(r.frames.len(), 3);
+    }
+
+    #[test]
+    fn push_treats_zero_frame_skip_as_one() {
+        let mut r = recorder();
+        r.toggle();
+        r.frame_skip = 0;
+        for _ in 0..5 {
+            r.push(&blank_frame());
+        }
+        assert_eq!(r.frames.len(), 5);
+    }
+
+    #[test]
+    fn push_stops_at_max_frames() {
+        let mut r = recorder();
+        r.toggle();
+        r.max_frames = 2;
+        for _ in 0..10 {
+            r.push(&blank_frame());
+        }
+        assert_eq!(r.frames.len(), 2);
+    }
+
+    #[test]
+    fn toggle_clears_buffered_frames_on_restart() {
+        let mut r = recorder();
+        r.toggle();
+        r.push(&blank_frame());
+        r.toggle(); // stop
+        r.toggle(); // restart
+        assert_eq!(r.frames.len(), 0);
+        assert_eq!(r.frames_seen, 0);
+    }
+}
@@ -0,0 +1,104 @@
+use nannou::wgpu;
+use nannou::Frame;
+
+/// Decouples the resolution the walker/texture/capture loop runs at from
+/// whatever the window displays. `WindowedRenderTarget` lets the window
+/// show a (possibly downscaled) preview of a texture rendered at its own
+/// resolution; `OffscreenRenderTarget` has no display step at all, for
+/// batch/headless runs that only care about the final capture.
+pub trait RenderTarget {
+    fn texture(&self) -> &wgpu::Texture;
+
+    /// Draws the render target's texture into the given frame. A no-op
+    /// for targets with nothing to present, e.g. `OffscreenRenderTarget`.
+    fn present(&self, frame: &Frame);
+}
+
+pub struct WindowedRenderTarget {
+    texture: wgpu::Texture,
+    reshaper: wgpu::TextureReshaper,
+}
+
+impl WindowedRenderTarget {
+    pub fn new(device: &wgpu::Device, width: u32, height: u32, msaa_samples: u32) -> Self {
+        let texture = build_texture(
+            device,
+            width,
+            height,
+            msaa_samples,
+            wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
+        );
+
+        let texture_view = texture.view().build();
+        let texture_component_type = texture.sample_type();
+        let reshaper = wgpu::TextureReshaper::new(
+            device,
+            &texture_view,
+            msaa_samples,
+            texture_component_type,
+            msaa_samples,
+            Frame::TEXTURE_FORMAT,
+        );
+
+        Self { texture, reshaper }
+    }
+}
+
+impl RenderTarget for WindowedRenderTarget {
+    fn texture(&self) -> &wgpu::Texture {
+        &self.texture
+    }
+
+    fn present(&self, frame: &Frame) {
+        let mut encoder = frame.command_encoder();
+        self.reshaper
+            .encode_render_pass(frame.texture_view(), &mut encoder);
+    }
+}
+
+pub struct OffscreenRenderTarget {
+    texture: wgpu::Texture,
+}
+
+impl OffscreenRenderTarget {
+    pub fn new(device: &wgpu::Device, width: u32, height: u32) -> Self {
+        let texture = build_texture(
+            device,
+            width,
+            height,
+            1,
+            wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
+        );
+
+        Self { texture }
+    }
+}
+
+impl RenderTarget for OffscreenRenderTarget {
+    fn texture(&self) -> &wgpu::Texture {
+        &self.texture
+    }
+
+    fn present(&self, _frame: &Frame) {
+        // nothing to display -- batch runs only care about the final capture.
+    }
+}
+
+fn build_texture(
+    device: &wgpu::Device,
+    width: u32,
+    height: u32,
+    msaa_samples: u32,
+    usage: wgpu::TextureUsages,
+) -> wgpu::Texture {
+    wgpu::TextureBuilder::new()
+        .size([width, height])
+        .usage(usage)
+        .sample_count(msaa_samples)
+        .format(Frame::TEXTURE_FORMAT)
+        .build(device)
+}